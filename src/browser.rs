@@ -0,0 +1,159 @@
+use servo::compositing::windowing::WindowEvent;
+use servo::ipc_channel::ipc::IpcSender;
+use servo::msg::constellation_msg::TraversalDirection;
+use servo::net_traits::net_error_list::NetError;
+use servo::script_traits::LoadData;
+use servo::servo_url::ServoUrl;
+use servo::BrowserId;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+/// A chrome-facing notification queued by a `WindowMethods` callback, to
+/// be folded into `Browser`'s state the next time `handle_servo_events()`
+/// runs.
+enum ServoEvent {
+    LoadStart,
+    LoadEnd,
+    LoadError,
+    HistoryChanged(Vec<LoadData>, usize),
+    TitleChanged(Option<String>),
+    StatusChanged(Option<String>),
+    FaviconChanged(ServoUrl),
+    NavigationRequested(ServoUrl, IpcSender<bool>),
+}
+
+/// Per-browser navigation/chrome state: current URL, title, loading
+/// spinner, and back/forward history. This is deliberately decoupled from
+/// `Window` (GL surface) and `WindowEntry` (the `Servo` instance), so a
+/// window can host several browsers and only one needs to be on screen at
+/// a time.
+pub struct Browser {
+    id: BrowserId,
+    pending: RefCell<VecDeque<ServoEvent>>,
+    url: RefCell<Option<ServoUrl>>,
+    title: RefCell<Option<String>>,
+    status: RefCell<Option<String>>,
+    favicon: RefCell<Option<ServoUrl>>,
+    is_loading: RefCell<bool>,
+    history: RefCell<Vec<LoadData>>,
+    history_index: RefCell<usize>,
+}
+
+impl Browser {
+    pub fn new(id: BrowserId) -> Browser {
+        Browser {
+            id,
+            pending: RefCell::new(VecDeque::new()),
+            url: RefCell::new(None),
+            title: RefCell::new(None),
+            status: RefCell::new(None),
+            favicon: RefCell::new(None),
+            is_loading: RefCell::new(false),
+            history: RefCell::new(Vec::new()),
+            history_index: RefCell::new(0),
+        }
+    }
+
+    pub fn id(&self) -> BrowserId {
+        self.id
+    }
+
+    pub fn url(&self) -> Option<ServoUrl> {
+        self.url.borrow().clone()
+    }
+
+    pub fn title(&self) -> Option<String> {
+        self.title.borrow().clone()
+    }
+
+    pub fn is_loading(&self) -> bool {
+        *self.is_loading.borrow()
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        *self.history_index.borrow() > 0
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        *self.history_index.borrow() + 1 < self.history.borrow().len()
+    }
+
+    // --- WindowMethods callbacks land here ---
+
+    pub(crate) fn on_load_start(&self) {
+        self.pending.borrow_mut().push_back(ServoEvent::LoadStart);
+    }
+
+    pub(crate) fn on_load_end(&self) {
+        self.pending.borrow_mut().push_back(ServoEvent::LoadEnd);
+    }
+
+    pub(crate) fn on_load_error(&self, _error: NetError, _url: String) {
+        self.pending.borrow_mut().push_back(ServoEvent::LoadError);
+    }
+
+    pub(crate) fn on_history_changed(&self, entries: Vec<LoadData>, current: usize) {
+        self.pending
+            .borrow_mut()
+            .push_back(ServoEvent::HistoryChanged(entries, current));
+    }
+
+    pub(crate) fn on_title_changed(&self, title: Option<String>) {
+        self.pending
+            .borrow_mut()
+            .push_back(ServoEvent::TitleChanged(title));
+    }
+
+    pub(crate) fn on_status_changed(&self, status: Option<String>) {
+        self.pending
+            .borrow_mut()
+            .push_back(ServoEvent::StatusChanged(status));
+    }
+
+    pub(crate) fn on_favicon_changed(&self, url: ServoUrl) {
+        self.pending
+            .borrow_mut()
+            .push_back(ServoEvent::FaviconChanged(url));
+    }
+
+    pub(crate) fn on_navigation_requested(&self, url: ServoUrl, chan: IpcSender<bool>) {
+        self.pending
+            .borrow_mut()
+            .push_back(ServoEvent::NavigationRequested(url, chan));
+    }
+
+    /// Drain the events queued by `WindowMethods` callbacks since the
+    /// last call, fold them into our navigation/chrome state, and answer
+    /// any pending navigation requests.
+    pub fn handle_servo_events(&self) {
+        let pending: Vec<_> = self.pending.borrow_mut().drain(..).collect();
+        for event in pending {
+            match event {
+                ServoEvent::LoadStart => *self.is_loading.borrow_mut() = true,
+                ServoEvent::LoadEnd => *self.is_loading.borrow_mut() = false,
+                ServoEvent::LoadError => *self.is_loading.borrow_mut() = false,
+                ServoEvent::HistoryChanged(entries, current) => {
+                    if let Some(entry) = entries.get(current) {
+                        *self.url.borrow_mut() = Some(entry.url.clone());
+                    }
+                    *self.history.borrow_mut() = entries;
+                    *self.history_index.borrow_mut() = current;
+                }
+                ServoEvent::TitleChanged(title) => *self.title.borrow_mut() = title,
+                ServoEvent::StatusChanged(status) => *self.status.borrow_mut() = status,
+                ServoEvent::FaviconChanged(url) => *self.favicon.borrow_mut() = Some(url),
+                ServoEvent::NavigationRequested(_url, chan) => {
+                    // Allow every navigation for now; this is the seam a
+                    // real embedder would hang a permission prompt off.
+                    chan.send(true).ok();
+                }
+            }
+        }
+    }
+
+    /// Build the `WindowEvent` to travel back (`Back`) or forward
+    /// (`Forward`) through this browser's history.
+    pub fn navigate(&self, direction: TraversalDirection) -> WindowEvent {
+        WindowEvent::Navigation(self.id, direction)
+    }
+}
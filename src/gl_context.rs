@@ -0,0 +1,134 @@
+use glutin;
+use std::mem;
+
+/// An explicit typestate machine for the GL context backing a `Window`.
+/// Context hand-offs (multi-window switching, moving work to another
+/// thread) are easy to get subtly wrong if "is it current right now" is
+/// only ever implicit; modeling it as an enum makes illegal states -
+/// rendering through a context nobody made current - an immediate panic
+/// in `get_proc_address()`/`present()` instead of a driver-dependent
+/// crash.
+pub enum GlContext {
+    /// An on-screen window's context, current on this thread.
+    Current(glutin::WindowedContext<glutin::PossiblyCurrent>),
+    /// An on-screen window's context, not current anywhere.
+    NotCurrent(glutin::WindowedContext<glutin::NotCurrent>),
+    /// An offscreen, OSMesa-style surface used for `--headless`
+    /// rendering, current on this thread. Only one context - windowed or
+    /// headless - can really be current on a thread at a time, so
+    /// multiple headless windows still hand this off between each other
+    /// exactly like windowed contexts do.
+    HeadlessCurrent(glutin::Context<glutin::PossiblyCurrent>),
+    /// An offscreen headless surface, not current anywhere.
+    HeadlessNotCurrent(glutin::Context<glutin::NotCurrent>),
+    /// Transient placeholder, only ever observed mid-`mem::replace`.
+    None,
+}
+
+impl GlContext {
+    pub fn windowed(context: glutin::WindowedContext<glutin::PossiblyCurrent>) -> GlContext {
+        GlContext::Current(context)
+    }
+
+    pub fn headless(context: glutin::Context<glutin::PossiblyCurrent>) -> GlContext {
+        GlContext::HeadlessCurrent(context)
+    }
+
+    /// Whether this is a `--headless` offscreen surface, current or not.
+    pub fn is_headless(&self) -> bool {
+        match self {
+            GlContext::HeadlessCurrent(_) | GlContext::HeadlessNotCurrent(_) => true,
+            GlContext::Current(_) | GlContext::NotCurrent(_) | GlContext::None => false,
+        }
+    }
+
+    /// Make this the current GL context on this thread, warning instead
+    /// of panicking if it already is (callers may race with a waker).
+    pub fn make_current(&mut self) {
+        *self = match mem::replace(self, GlContext::None) {
+            GlContext::Current(ctx) => {
+                eprintln!("GlContext::make_current: already current");
+                GlContext::Current(ctx)
+            }
+            GlContext::NotCurrent(ctx) => match unsafe { ctx.make_current() } {
+                Ok(ctx) => GlContext::Current(ctx),
+                Err((ctx, err)) => {
+                    eprintln!("Failed to make GL context current: {:?}", err);
+                    GlContext::NotCurrent(ctx)
+                }
+            },
+            GlContext::HeadlessCurrent(ctx) => {
+                eprintln!("GlContext::make_current: already current");
+                GlContext::HeadlessCurrent(ctx)
+            }
+            GlContext::HeadlessNotCurrent(ctx) => match unsafe { ctx.make_current() } {
+                Ok(ctx) => GlContext::HeadlessCurrent(ctx),
+                Err((ctx, err)) => {
+                    eprintln!("Failed to make headless GL context current: {:?}", err);
+                    GlContext::HeadlessNotCurrent(ctx)
+                }
+            },
+            GlContext::None => GlContext::None,
+        };
+    }
+
+    /// Release this thread's claim on the GL context, so another window
+    /// can make its own context current instead.
+    pub fn make_not_current(&mut self) {
+        *self = match mem::replace(self, GlContext::None) {
+            GlContext::Current(ctx) => match unsafe { ctx.make_not_current() } {
+                Ok(ctx) => GlContext::NotCurrent(ctx),
+                Err((ctx, err)) => {
+                    eprintln!("Failed to make GL context not current: {:?}", err);
+                    GlContext::Current(ctx)
+                }
+            },
+            GlContext::NotCurrent(ctx) => {
+                eprintln!("GlContext::make_not_current: already not current");
+                GlContext::NotCurrent(ctx)
+            }
+            GlContext::HeadlessCurrent(ctx) => match unsafe { ctx.make_not_current() } {
+                Ok(ctx) => GlContext::HeadlessNotCurrent(ctx),
+                Err((ctx, err)) => {
+                    eprintln!("Failed to make headless GL context not current: {:?}", err);
+                    GlContext::HeadlessCurrent(ctx)
+                }
+            },
+            GlContext::HeadlessNotCurrent(ctx) => {
+                eprintln!("GlContext::make_not_current: already not current");
+                GlContext::HeadlessNotCurrent(ctx)
+            }
+            GlContext::None => GlContext::None,
+        };
+    }
+
+    pub fn get_proc_address(&self, symbol: &str) -> *const () {
+        match self {
+            GlContext::Current(ctx) => ctx.get_proc_address(symbol) as *const _,
+            GlContext::HeadlessCurrent(ctx) => ctx.get_proc_address(symbol) as *const _,
+            GlContext::NotCurrent(_) | GlContext::HeadlessNotCurrent(_) | GlContext::None => {
+                panic!("Can't load GL entry points through a context that isn't current")
+            }
+        }
+    }
+
+    /// Notify a windowed context's GL surface that the window resized.
+    /// No-op for headless surfaces, which have a fixed offscreen size.
+    pub fn resize(&self, size: glutin::dpi::PhysicalSize) {
+        if let GlContext::Current(ctx) = self {
+            ctx.resize(size);
+        }
+    }
+
+    /// Present the frame, if this context is backed by a visible window.
+    /// Headless surfaces have nothing to present to.
+    pub fn present(&self) {
+        match self {
+            GlContext::Current(ctx) => ctx.swap_buffers().unwrap(),
+            GlContext::HeadlessCurrent(_) => {}
+            GlContext::NotCurrent(_) | GlContext::HeadlessNotCurrent(_) | GlContext::None => {
+                panic!("Can't present a context that isn't current")
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use glutin;
+use servo::msg::constellation_msg::{Key, KeyModifiers};
+
+/// Translate a glutin virtual keycode into Servo's `Key`. Returns `None`
+/// for keys Servo has no concept of (media keys, OS keys, ...).
+pub fn key_from_keycode(keycode: glutin::VirtualKeyCode) -> Option<Key> {
+    use glutin::VirtualKeyCode as Vk;
+    Some(match keycode {
+        Vk::A => Key::A,
+        Vk::B => Key::B,
+        Vk::C => Key::C,
+        Vk::D => Key::D,
+        Vk::E => Key::E,
+        Vk::F => Key::F,
+        Vk::G => Key::G,
+        Vk::H => Key::H,
+        Vk::I => Key::I,
+        Vk::J => Key::J,
+        Vk::K => Key::K,
+        Vk::L => Key::L,
+        Vk::M => Key::M,
+        Vk::N => Key::N,
+        Vk::O => Key::O,
+        Vk::P => Key::P,
+        Vk::Q => Key::Q,
+        Vk::R => Key::R,
+        Vk::S => Key::S,
+        Vk::T => Key::T,
+        Vk::U => Key::U,
+        Vk::V => Key::V,
+        Vk::W => Key::W,
+        Vk::X => Key::X,
+        Vk::Y => Key::Y,
+        Vk::Z => Key::Z,
+        Vk::Key0 => Key::Num0,
+        Vk::Key1 => Key::Num1,
+        Vk::Key2 => Key::Num2,
+        Vk::Key3 => Key::Num3,
+        Vk::Key4 => Key::Num4,
+        Vk::Key5 => Key::Num5,
+        Vk::Key6 => Key::Num6,
+        Vk::Key7 => Key::Num7,
+        Vk::Key8 => Key::Num8,
+        Vk::Key9 => Key::Num9,
+        Vk::Space => Key::Space,
+        Vk::Return => Key::Enter,
+        Vk::Back => Key::Backspace,
+        Vk::Delete => Key::Delete,
+        Vk::Tab => Key::Tab,
+        Vk::Escape => Key::Escape,
+        Vk::Left => Key::Left,
+        Vk::Right => Key::Right,
+        Vk::Up => Key::Up,
+        Vk::Down => Key::Down,
+        Vk::Home => Key::Home,
+        Vk::End => Key::End,
+        Vk::PageUp => Key::PageUp,
+        Vk::PageDown => Key::PageDown,
+        Vk::Equals => Key::Equal,
+        Vk::Minus => Key::Minus,
+        Vk::Period => Key::Period,
+        Vk::Comma => Key::Comma,
+        Vk::Slash => Key::Slash,
+        Vk::Semicolon => Key::Semicolon,
+        _ => return None,
+    })
+}
+
+/// A virtual keycode is "char-producing" when it normally comes paired
+/// with a `ReceivedCharacter` event whose char we want to carry alongside
+/// the `Key`, instead of forwarding it with no char at all.
+pub fn is_char_producing(keycode: glutin::VirtualKeyCode) -> bool {
+    use glutin::VirtualKeyCode as Vk;
+    match keycode {
+        Vk::A
+        | Vk::B
+        | Vk::C
+        | Vk::D
+        | Vk::E
+        | Vk::F
+        | Vk::G
+        | Vk::H
+        | Vk::I
+        | Vk::J
+        | Vk::K
+        | Vk::L
+        | Vk::M
+        | Vk::N
+        | Vk::O
+        | Vk::P
+        | Vk::Q
+        | Vk::R
+        | Vk::S
+        | Vk::T
+        | Vk::U
+        | Vk::V
+        | Vk::W
+        | Vk::X
+        | Vk::Y
+        | Vk::Z
+        | Vk::Key0
+        | Vk::Key1
+        | Vk::Key2
+        | Vk::Key3
+        | Vk::Key4
+        | Vk::Key5
+        | Vk::Key6
+        | Vk::Key7
+        | Vk::Key8
+        | Vk::Key9
+        | Vk::Space
+        | Vk::Return
+        | Vk::Back
+        | Vk::Tab
+        | Vk::Equals
+        | Vk::Minus
+        | Vk::Period
+        | Vk::Comma
+        | Vk::Slash
+        | Vk::Semicolon => true,
+        _ => false,
+    }
+}
+
+/// Translate glutin's modifier bitset into Servo's.
+pub fn modifiers_from_state(mods: glutin::ModifiersState) -> KeyModifiers {
+    let mut result = KeyModifiers::empty();
+    if mods.shift {
+        result.insert(KeyModifiers::SHIFT);
+    }
+    if mods.ctrl {
+        result.insert(KeyModifiers::CONTROL);
+    }
+    if mods.alt {
+        result.insert(KeyModifiers::ALT);
+    }
+    if mods.logo {
+        result.insert(KeyModifiers::SUPER);
+    }
+    result
+}
@@ -1,48 +1,92 @@
+extern crate clipboard;
 extern crate glutin;
 extern crate servo;
 
-use servo::gl;
-use glutin::GlContext;
-use servo::BrowserId;
-use servo::compositing::compositor_thread::EventLoopWaker;
+mod browser;
+mod gl_context;
+mod keymap;
+mod window;
+
 use servo::compositing::windowing::{WindowEvent, WindowMethods};
-use servo::euclid::{Point2D, Size2D, TypedPoint2D, TypedRect, TypedScale, TypedSize2D,
-                    TypedVector2D};
-use servo::ipc_channel::ipc;
-use servo::msg::constellation_msg::{Key, KeyModifiers};
-use servo::net_traits::net_error_list::NetError;
-use servo::script_traits::{LoadData, TouchEventType};
+use servo::euclid::{TypedPoint2D, TypedVector2D};
+use servo::msg::constellation_msg::{KeyModifiers, KeyState, TraversalDirection};
+use servo::script_traits::TouchEventType;
 use servo::servo_config::opts;
 use servo::servo_config::resource_files::set_resources_path;
-use servo::servo_geometry::DeviceIndependentPixel;
 use servo::servo_url::ServoUrl;
-use servo::style_traits::DevicePixel;
-use servo::style_traits::cursor::CursorKind;
+use servo::webrender_api::ScrollLocation;
 use std::env;
-use std::rc::Rc;
-use std::sync::Arc;
 
-pub struct GlutinEventLoopWaker {
-    proxy: Arc<glutin::EventsLoopProxy>,
+/// Pixel step used for one notch of a mouse wheel or one arrow-key press.
+const LINE_HEIGHT: f64 = 38.0;
+
+/// How much one pixel of Ctrl+MouseWheel delta scales a trackpad pinch
+/// gesture by.
+const PINCH_ZOOM_SENSITIVITY: f32 = 0.01;
+
+fn is_scroll_key(keycode: glutin::VirtualKeyCode) -> bool {
+    match keycode {
+        glutin::VirtualKeyCode::Home
+        | glutin::VirtualKeyCode::End
+        | glutin::VirtualKeyCode::PageUp
+        | glutin::VirtualKeyCode::PageDown
+        | glutin::VirtualKeyCode::Up
+        | glutin::VirtualKeyCode::Down
+        | glutin::VirtualKeyCode::Left
+        | glutin::VirtualKeyCode::Right => true,
+        _ => false,
+    }
 }
 
-impl EventLoopWaker for GlutinEventLoopWaker {
-    // Use by servo to share the "event loop waker" across threads
-    fn clone(&self) -> Box<EventLoopWaker + Send> {
-        Box::new(GlutinEventLoopWaker {
-            proxy: self.proxy.clone(),
-        })
-    }
-    // Called by servo when the main thread needs to wake up
-    fn wake(&self) {
-        self.proxy.wakeup().expect("wakeup eventloop failed");
+/// Ctrl held with one of these is a copy/cut/paste shortcut, not character
+/// input: `C`/`V`/`X` are otherwise char-producing keys (see
+/// `keymap::is_char_producing`), so without this they'd sit in
+/// `pending_char_key` waiting for a `ReceivedCharacter` that Ctrl held down
+/// never produces.
+fn is_clipboard_key(keycode: glutin::VirtualKeyCode) -> bool {
+    match keycode {
+        glutin::VirtualKeyCode::C | glutin::VirtualKeyCode::V | glutin::VirtualKeyCode::X => true,
+        _ => false,
     }
 }
 
-struct Window {
-    glutin_window: glutin::GlWindow,
-    waker: Box<EventLoopWaker>,
-    gl: Rc<gl::Gl>,
+/// Where to scroll from when there's no recent mouse position in this
+/// window: the window's center.
+fn scroll_origin(
+    entry: &window::WindowEntry,
+) -> TypedPoint2D<i32, servo::style_traits::DevicePixel> {
+    let (x, y) = entry.window.pointer().unwrap_or_else(|| entry.window.center());
+    TypedPoint2D::new(x as i32, y as i32)
+}
+
+/// Home/End jump to the very top/bottom of the document regardless of its
+/// height; PageUp/PageDown step by one viewport; the arrows step by one
+/// line.
+fn scroll_location_for_key(
+    keycode: glutin::VirtualKeyCode,
+    entry: &window::WindowEntry,
+) -> ScrollLocation {
+    match keycode {
+        glutin::VirtualKeyCode::Home => ScrollLocation::Start,
+        glutin::VirtualKeyCode::End => ScrollLocation::End,
+        glutin::VirtualKeyCode::PageUp => {
+            let page_height = entry.window.framebuffer_size().height as f64;
+            ScrollLocation::Delta(TypedVector2D::new(0.0, page_height))
+        }
+        glutin::VirtualKeyCode::PageDown => {
+            let page_height = entry.window.framebuffer_size().height as f64;
+            ScrollLocation::Delta(TypedVector2D::new(0.0, -page_height))
+        }
+        glutin::VirtualKeyCode::Up => ScrollLocation::Delta(TypedVector2D::new(0.0, LINE_HEIGHT)),
+        glutin::VirtualKeyCode::Down => {
+            ScrollLocation::Delta(TypedVector2D::new(0.0, -LINE_HEIGHT))
+        }
+        glutin::VirtualKeyCode::Left => ScrollLocation::Delta(TypedVector2D::new(LINE_HEIGHT, 0.0)),
+        glutin::VirtualKeyCode::Right => {
+            ScrollLocation::Delta(TypedVector2D::new(-LINE_HEIGHT, 0.0))
+        }
+        _ => unreachable!("is_scroll_key filters the keycode"),
+    }
 }
 
 fn main() {
@@ -50,230 +94,408 @@ fn main() {
 
     let mut event_loop = glutin::EventsLoop::new();
 
-    let builder = glutin::WindowBuilder::new().with_dimensions(800, 600);
-    let gl_version = glutin::GlRequest::Specific(glutin::Api::OpenGl, (3, 2));
-    let context = glutin::ContextBuilder::new()
-        .with_gl(gl_version)
-        .with_vsync(true);
-    let window = glutin::GlWindow::new(builder, context, &event_loop).unwrap();
-
-    window.show();
-
-    let gl = unsafe {
-        window
-            .context()
-            .make_current()
-            .expect("Couldn't make window current");
-        gl::GlFns::load_with(|s| window.context().get_proc_address(s) as *const _)
-    };
-
-    let event_loop_waker = Box::new(GlutinEventLoopWaker {
-        proxy: Arc::new(event_loop.create_proxy()),
-    });
-
     let path = env::current_dir().unwrap().join("resources");
     let path = path.to_str().unwrap().to_string();
     set_resources_path(Some(path));
     opts::set_defaults(opts::default_opts());
 
-    let window = Rc::new(Window {
-        glutin_window: window,
-        waker: event_loop_waker,
-        gl: gl,
-    });
-
-    let mut servo = servo::Servo::new(window.clone());
+    // Render into an offscreen surface instead of a visible window, for
+    // running under CI or other displayless environments.
+    let headless = env::args().any(|arg| arg == "--headless");
 
     let url = ServoUrl::parse("https://servo.org").unwrap();
-    let (sender, receiver) = ipc::channel().unwrap();
-    servo.handle_events(vec![WindowEvent::NewBrowser(url, sender)]);
-    let browser_id = receiver.recv().unwrap();
-    servo.handle_events(vec![WindowEvent::SelectBrowser(browser_id)]);
-
-    let mut pointer = (0.0, 0.0);
+    window::create_window(&event_loop, url, headless);
 
     event_loop.run_forever(|event| {
         // Blocked until user event or until servo unblocks it
         match event {
-            // This is the event triggered by GlutinEventLoopWaker
+            // This is the event triggered by GlutinEventLoopWaker. We don't
+            // know which window asked to be woken up, so pump them all.
             glutin::Event::Awakened => {
-                servo.handle_events(vec![]);
+                window::wake_all();
             }
 
-            // Mousemove
-            glutin::Event::WindowEvent {
-                event:
-                    glutin::WindowEvent::CursorMoved {
-                        position: (x, y), ..
-                    },
-                ..
-            } => {
-                pointer = (x, y);
-                let event =
-                    WindowEvent::MouseWindowMoveEventClass(TypedPoint2D::new(x as f32, y as f32));
-                servo.handle_events(vec![event]);
-            }
-
-            // reload when R is pressed
-            glutin::Event::WindowEvent {
-                event:
+            glutin::Event::WindowEvent { window_id, event } => {
+                // Ctrl+T/Ctrl+W are chrome shortcuts handled by the window
+                // registry itself, everything else is routed to the
+                // `Servo` instance that owns `window_id`.
+                match event {
                     glutin::WindowEvent::KeyboardInput {
                         input:
                             glutin::KeyboardInput {
                                 state: glutin::ElementState::Pressed,
-                                virtual_keycode: Some(glutin::VirtualKeyCode::R),
+                                virtual_keycode: Some(glutin::VirtualKeyCode::T),
+                                modifiers: glutin::ModifiersState { ctrl: true, .. },
                                 ..
                             },
                         ..
-                    },
-                ..
-            } => {
-                let event = WindowEvent::Reload(browser_id);
-                servo.handle_events(vec![event]);
-            }
-
-            // Scrolling
-            glutin::Event::WindowEvent {
-                event: glutin::WindowEvent::MouseWheel { delta, phase, .. },
-                ..
-            } => {
-                let pointer = TypedPoint2D::new(pointer.0 as i32, pointer.1 as i32);
-                let (dx, dy) = match delta {
-                    glutin::MouseScrollDelta::LineDelta(dx, dy) => {
-                        (dx, dy * 38.0 /*line height*/)
+                    } => {
+                        let url = ServoUrl::parse("https://servo.org").unwrap();
+                        window::create_window(&event_loop, url, headless);
+                        return glutin::ControlFlow::Continue;
                     }
-                    glutin::MouseScrollDelta::PixelDelta(dx, dy) => (dx, dy),
-                };
-                let scroll_location =
-                    servo::webrender_api::ScrollLocation::Delta(TypedVector2D::new(dx, dy));
-                let phase = match phase {
-                    glutin::TouchPhase::Started => TouchEventType::Down,
-                    glutin::TouchPhase::Moved => TouchEventType::Move,
-                    glutin::TouchPhase::Ended => TouchEventType::Up,
-                    glutin::TouchPhase::Cancelled => TouchEventType::Up,
-                };
-                let event = WindowEvent::Scroll(scroll_location, pointer, phase);
-                servo.handle_events(vec![event]);
-            }
-            glutin::Event::WindowEvent {
-                event: glutin::WindowEvent::Resized(width, height),
-                ..
-            } => {
-                let event = WindowEvent::Resize;
-                servo.handle_events(vec![event]);
-                window.glutin_window.resize(width, height);
-            }
-            _ => {}
-        }
-        glutin::ControlFlow::Continue
-    });
-}
-
-impl WindowMethods for Window {
-    fn prepare_for_composite(&self, _width: usize, _height: usize) -> bool {
-        true
-    }
-
-    fn present(&self) {
-        self.glutin_window.swap_buffers().unwrap();
-    }
-
-    fn supports_clipboard(&self) -> bool {
-        false
-    }
-
-    fn create_event_loop_waker(&self) -> Box<EventLoopWaker> {
-        self.waker.clone()
-    }
-
-    fn gl(&self) -> Rc<gl::Gl> {
-        self.gl.clone()
-    }
 
-    fn hidpi_factor(&self) -> TypedScale<f32, DeviceIndependentPixel, DevicePixel> {
-        TypedScale::new(self.glutin_window.hidpi_factor())
-    }
-
-    fn framebuffer_size(&self) -> TypedSize2D<u32, DevicePixel> {
-        let (width, height) = self.glutin_window.get_inner_size().unwrap();
-        let scale_factor = self.glutin_window.hidpi_factor() as u32;
-        TypedSize2D::new(scale_factor * width, scale_factor * height)
-    }
-
-    fn window_rect(&self) -> TypedRect<u32, DevicePixel> {
-        TypedRect::new(TypedPoint2D::new(0, 0), self.framebuffer_size())
-    }
-
-    fn size(&self) -> TypedSize2D<f32, DeviceIndependentPixel> {
-        let (width, height) = self.glutin_window.get_inner_size().unwrap();
-        TypedSize2D::new(width as f32, height as f32)
-    }
+                    glutin::WindowEvent::KeyboardInput {
+                        input:
+                            glutin::KeyboardInput {
+                                state: glutin::ElementState::Pressed,
+                                virtual_keycode: Some(glutin::VirtualKeyCode::W),
+                                modifiers: glutin::ModifiersState { ctrl: true, .. },
+                                ..
+                            },
+                        ..
+                    } => {
+                        if window::close_window(window_id) {
+                            return glutin::ControlFlow::Break;
+                        }
+                        return glutin::ControlFlow::Continue;
+                    }
 
-    fn client_window(&self, _id: BrowserId) -> (Size2D<u32>, Point2D<i32>) {
-        let (width, height) = self.glutin_window.get_inner_size().unwrap();
-        let (x, y) = self.glutin_window.get_position().unwrap();
-        (Size2D::new(width, height), Point2D::new(x as i32, y as i32))
-    }
+                    glutin::WindowEvent::Focused(true) => {
+                        window::set_focused_window_id(window_id);
+                    }
 
-    fn set_inner_size(&self, _id: BrowserId, _size: Size2D<u32>) {}
+                    glutin::WindowEvent::CursorMoved {
+                        position: (x, y), ..
+                    } => {
+                        window::with_window(window_id, |entry| {
+                            entry.window.set_pointer(x, y);
+                            if let Some(browser_id) = entry.browser_id.get() {
+                                entry.dispatch(vec![
+                                    WindowEvent::SelectBrowser(browser_id),
+                                    WindowEvent::MouseWindowMoveEventClass(TypedPoint2D::new(
+                                        x as f32, y as f32,
+                                    )),
+                                ]);
+                            }
+                        });
+                    }
 
-    fn set_position(&self, _id: BrowserId, _point: Point2D<i32>) {}
+                    // Ctrl+R reloads. `R` is otherwise a char-producing
+                    // key (see `keymap::is_char_producing`), so without
+                    // the Ctrl guard the letter could never be typed into
+                    // a focused text field.
+                    glutin::WindowEvent::KeyboardInput {
+                        input:
+                            glutin::KeyboardInput {
+                                state: glutin::ElementState::Pressed,
+                                virtual_keycode: Some(glutin::VirtualKeyCode::R),
+                                modifiers: glutin::ModifiersState { ctrl: true, .. },
+                                ..
+                            },
+                        ..
+                    } => {
+                        window::with_window(window_id, |entry| {
+                            if let Some(browser_id) = entry.browser_id.get() {
+                                entry.dispatch(vec![WindowEvent::Reload(browser_id)]);
+                            }
+                        });
+                    }
 
-    fn set_fullscreen_state(&self, _id: BrowserId, _state: bool) {}
+                    // Alt+Left / Alt+Right walk the focused browser's history.
+                    glutin::WindowEvent::KeyboardInput {
+                        input:
+                            glutin::KeyboardInput {
+                                state: glutin::ElementState::Pressed,
+                                virtual_keycode: Some(keycode @ glutin::VirtualKeyCode::Left),
+                                modifiers: glutin::ModifiersState { alt: true, .. },
+                                ..
+                            },
+                        ..
+                    }
+                    | glutin::WindowEvent::KeyboardInput {
+                        input:
+                            glutin::KeyboardInput {
+                                state: glutin::ElementState::Pressed,
+                                virtual_keycode: Some(keycode @ glutin::VirtualKeyCode::Right),
+                                modifiers: glutin::ModifiersState { alt: true, .. },
+                                ..
+                            },
+                        ..
+                    } => {
+                        let direction = if keycode == glutin::VirtualKeyCode::Left {
+                            TraversalDirection::Back(1)
+                        } else {
+                            TraversalDirection::Forward(1)
+                        };
+                        window::with_window(window_id, |entry| {
+                            if let Some(browser_id) = entry.browser_id.get() {
+                                let event = entry.window.navigate_event(browser_id, direction);
+                                entry.dispatch(vec![event]);
+                            }
+                        });
+                    }
 
-    fn set_page_title(&self, _id: BrowserId, title: Option<String>) {
-        self.glutin_window.set_title(match title {
-            Some(ref title) => title,
-            None => "",
-        });
-    }
+                    // Ctrl+L would normally focus a URL bar; this example
+                    // has no chrome UI to focus, so print the current URL
+                    // as a stand-in.
+                    glutin::WindowEvent::KeyboardInput {
+                        input:
+                            glutin::KeyboardInput {
+                                state: glutin::ElementState::Pressed,
+                                virtual_keycode: Some(glutin::VirtualKeyCode::L),
+                                modifiers: glutin::ModifiersState { ctrl: true, .. },
+                                ..
+                            },
+                        ..
+                    } => {
+                        window::with_window(window_id, |entry| {
+                            println!("URL: {:?}", entry.window.url(entry.browser_id.get()));
+                        });
+                    }
 
-    fn status(&self, _id: BrowserId, _status: Option<String>) {}
+                    // Ctrl+C/Ctrl+X/Ctrl+V trigger the focused element's
+                    // copy/cut/paste editing command. Forwarded as a plain
+                    // `KeyEvent` (no char) straight to the page, bypassing
+                    // the char-producing wait below.
+                    glutin::WindowEvent::KeyboardInput {
+                        input:
+                            glutin::KeyboardInput {
+                                state: glutin::ElementState::Pressed,
+                                virtual_keycode: Some(keycode),
+                                modifiers: glutin::ModifiersState { ctrl: true, .. },
+                                ..
+                            },
+                        ..
+                    } if is_clipboard_key(keycode) => {
+                        window::with_window(window_id, |entry| {
+                            if let Some(browser_id) = entry.browser_id.get() {
+                                let key = keymap::key_from_keycode(keycode)
+                                    .expect("C/V/X always map to a Key");
+                                entry.dispatch(vec![WindowEvent::KeyEvent(
+                                    Some(browser_id),
+                                    None,
+                                    key,
+                                    KeyState::Pressed,
+                                    KeyModifiers::CONTROL,
+                                )]);
+                            }
+                        });
+                    }
 
-    fn allow_navigation(&self, _id: BrowserId, _url: ServoUrl, chan: ipc::IpcSender<bool>) {
-        chan.send(true).ok();
-    }
+                    // Ctrl+=/Ctrl+- step the page zoom; Ctrl+0 resets it.
+                    glutin::WindowEvent::KeyboardInput {
+                        input:
+                            glutin::KeyboardInput {
+                                state: glutin::ElementState::Pressed,
+                                virtual_keycode: Some(glutin::VirtualKeyCode::Equals),
+                                modifiers: glutin::ModifiersState { ctrl: true, .. },
+                                ..
+                            },
+                        ..
+                    } => {
+                        window::with_window(window_id, |entry| {
+                            let factor = entry.window.zoom_in();
+                            println!("Zoom: {:.0}%", entry.window.zoom_level() * 100.0);
+                            entry.dispatch(vec![WindowEvent::Zoom(factor)]);
+                        });
+                    }
 
-    fn load_start(&self, _id: BrowserId) {}
+                    glutin::WindowEvent::KeyboardInput {
+                        input:
+                            glutin::KeyboardInput {
+                                state: glutin::ElementState::Pressed,
+                                virtual_keycode: Some(glutin::VirtualKeyCode::Minus),
+                                modifiers: glutin::ModifiersState { ctrl: true, .. },
+                                ..
+                            },
+                        ..
+                    } => {
+                        window::with_window(window_id, |entry| {
+                            let factor = entry.window.zoom_out();
+                            println!("Zoom: {:.0}%", entry.window.zoom_level() * 100.0);
+                            entry.dispatch(vec![WindowEvent::Zoom(factor)]);
+                        });
+                    }
 
-    fn load_end(&self, _id: BrowserId) {}
+                    glutin::WindowEvent::KeyboardInput {
+                        input:
+                            glutin::KeyboardInput {
+                                state: glutin::ElementState::Pressed,
+                                virtual_keycode: Some(glutin::VirtualKeyCode::Key0),
+                                modifiers: glutin::ModifiersState { ctrl: true, .. },
+                                ..
+                            },
+                        ..
+                    } => {
+                        window::with_window(window_id, |entry| {
+                            entry.window.reset_zoom();
+                            println!("Zoom: {:.0}%", entry.window.zoom_level() * 100.0);
+                            entry.dispatch(vec![WindowEvent::ResetZoom]);
+                        });
+                    }
 
-    fn load_error(&self, _id: BrowserId, _: NetError, _url: String) {}
+                    // Ctrl+MouseWheel is how trackpads report a pinch
+                    // gesture; treat it as a compositor-level pinch zoom
+                    // rather than a page scroll.
+                    glutin::WindowEvent::MouseWheel {
+                        delta,
+                        modifiers: glutin::ModifiersState { ctrl: true, .. },
+                        ..
+                    } => {
+                        let dy = match delta {
+                            glutin::MouseScrollDelta::LineDelta(_, dy) => dy * LINE_HEIGHT,
+                            glutin::MouseScrollDelta::PixelDelta(_, dy) => dy,
+                        };
+                        let factor = 1.0 + dy as f32 * PINCH_ZOOM_SENSITIVITY;
+                        window::with_window(window_id, |entry| {
+                            entry.dispatch(vec![WindowEvent::PinchZoom(factor)]);
+                        });
+                    }
 
-    fn head_parsed(&self, _id: BrowserId) {}
+                    // Scrolling
+                    glutin::WindowEvent::MouseWheel { delta, phase, .. } => {
+                        let (dx, dy) = match delta {
+                            glutin::MouseScrollDelta::LineDelta(dx, dy) => (dx, dy * LINE_HEIGHT),
+                            glutin::MouseScrollDelta::PixelDelta(dx, dy) => (dx, dy),
+                        };
+                        let scroll_location =
+                            servo::webrender_api::ScrollLocation::Delta(TypedVector2D::new(dx, dy));
+                        let phase = match phase {
+                            glutin::TouchPhase::Started => TouchEventType::Down,
+                            glutin::TouchPhase::Moved => TouchEventType::Move,
+                            glutin::TouchPhase::Ended => TouchEventType::Up,
+                            glutin::TouchPhase::Cancelled => TouchEventType::Up,
+                        };
+                        window::with_window(window_id, |entry| {
+                            let origin = scroll_origin(entry);
+                            entry.dispatch(vec![WindowEvent::Scroll(
+                                scroll_location,
+                                origin,
+                                phase,
+                            )]);
+                        });
+                    }
 
-    fn history_changed(&self, _id: BrowserId, _entries: Vec<LoadData>, _current: usize) {}
+                    // Home/End jump to the very top/bottom of the page;
+                    // PageUp/PageDown step by one viewport; the arrow keys
+                    // step by one line. Also forwarded as a `KeyEvent`
+                    // (like the generic arm below does for every other
+                    // key) so a focused `<input>`/`<textarea>` still gets
+                    // to move its own text cursor with these keys instead
+                    // of the page only ever scrolling.
+                    glutin::WindowEvent::KeyboardInput {
+                        input:
+                            glutin::KeyboardInput {
+                                state: glutin::ElementState::Pressed,
+                                virtual_keycode: Some(keycode),
+                                modifiers,
+                                ..
+                            },
+                        ..
+                    } if is_scroll_key(keycode) => {
+                        window::with_window(window_id, |entry| {
+                            let mut events = Vec::new();
+                            if let Some(browser_id) = entry.browser_id.get() {
+                                if let Some(key) = keymap::key_from_keycode(keycode) {
+                                    let key_state = if entry.window.is_key_held(keycode) {
+                                        KeyState::Repeated
+                                    } else {
+                                        KeyState::Pressed
+                                    };
+                                    entry.window.set_key_held(keycode, true);
+                                    events.push(WindowEvent::KeyEvent(
+                                        Some(browser_id),
+                                        None,
+                                        key,
+                                        key_state,
+                                        keymap::modifiers_from_state(modifiers),
+                                    ));
+                                }
+                            }
+                            let origin = scroll_origin(entry);
+                            let scroll_location = scroll_location_for_key(keycode, entry);
+                            events.push(WindowEvent::Scroll(
+                                scroll_location,
+                                origin,
+                                TouchEventType::Move,
+                            ));
+                            entry.dispatch(events);
+                        });
+                    }
 
-    fn set_cursor(&self, cursor: CursorKind) {
-        let cursor = match cursor {
-            CursorKind::Pointer => glutin::MouseCursor::Hand,
-            _ => glutin::MouseCursor::Default,
-        };
-        self.glutin_window.set_cursor(cursor);
-    }
+                    glutin::WindowEvent::Resized(width, height) => {
+                        window::with_window(window_id, |entry| {
+                            entry.dispatch(vec![WindowEvent::Resize]);
+                            entry.window.resize(width, height);
+                        });
+                    }
 
-    fn set_favicon(&self, _id: BrowserId, _url: ServoUrl) {}
+                    glutin::WindowEvent::Closed => {
+                        if window::close_window(window_id) {
+                            return glutin::ControlFlow::Break;
+                        }
+                    }
 
-    fn handle_key(
-        &self,
-        _id: Option<BrowserId>,
-        _ch: Option<char>,
-        _key: Key,
-        _mods: KeyModifiers,
-    ) {
-    }
+                    // Everything else that maps to a Servo `Key` gets
+                    // forwarded into the page. Char-producing keys wait
+                    // for the `ReceivedCharacter` that follows them so the
+                    // two can be combined into a single `KeyEvent`.
+                    glutin::WindowEvent::KeyboardInput {
+                        input:
+                            glutin::KeyboardInput {
+                                state,
+                                virtual_keycode: Some(keycode),
+                                modifiers,
+                                ..
+                            },
+                        ..
+                    } => {
+                        if let Some(key) = keymap::key_from_keycode(keycode) {
+                            let mods = keymap::modifiers_from_state(modifiers);
+                            window::with_window(window_id, |entry| {
+                                let key_state = match state {
+                                    glutin::ElementState::Pressed
+                                        if entry.window.is_key_held(keycode) =>
+                                    {
+                                        KeyState::Repeated
+                                    }
+                                    glutin::ElementState::Pressed => KeyState::Pressed,
+                                    glutin::ElementState::Released => KeyState::Released,
+                                };
+                                entry
+                                    .window
+                                    .set_key_held(keycode, state == glutin::ElementState::Pressed);
+                                if state == glutin::ElementState::Pressed
+                                    && keymap::is_char_producing(keycode)
+                                {
+                                    entry.window.set_pending_char_key((key, key_state, mods));
+                                } else if let Some(browser_id) = entry.browser_id.get() {
+                                    entry.dispatch(vec![WindowEvent::KeyEvent(
+                                        Some(browser_id),
+                                        None,
+                                        key,
+                                        key_state,
+                                        mods,
+                                    )]);
+                                }
+                            });
+                        }
+                    }
 
-    fn handle_panic(&self, _id: BrowserId, _reason: String, _backtrace: Option<String>) {}
+                    glutin::WindowEvent::ReceivedCharacter(ch) => {
+                        window::with_window(window_id, |entry| {
+                            if let Some((key, key_state, mods)) = entry.window.take_pending_char_key() {
+                                let ch = if ch.is_control() { None } else { Some(ch) };
+                                if let Some(browser_id) = entry.browser_id.get() {
+                                    entry.dispatch(vec![WindowEvent::KeyEvent(
+                                        Some(browser_id),
+                                        ch,
+                                        key,
+                                        key_state,
+                                        mods,
+                                    )]);
+                                }
+                            }
+                        });
+                    }
 
-    fn screen_avail_size(&self, _id: BrowserId) -> Size2D<u32> {
-        let monitor = self.glutin_window.get_current_monitor();
-        let (monitor_width, monitor_height) = monitor.get_dimensions();
-        Size2D::new(monitor_width, monitor_height)
-    }
+                    _ => {}
+                }
+            }
 
-    fn screen_size(&self, _id: BrowserId) -> Size2D<u32> {
-        let monitor = self.glutin_window.get_current_monitor();
-        let (monitor_width, monitor_height) = monitor.get_dimensions();
-        Size2D::new(monitor_width, monitor_height)
-    }
+            _ => {}
+        }
+        glutin::ControlFlow::Continue
+    });
 }
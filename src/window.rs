@@ -0,0 +1,644 @@
+use browser::Browser;
+use clipboard::{ClipboardContext, ClipboardProvider};
+use gl_context::GlContext;
+use glutin;
+use servo::compositing::compositor_thread::EventLoopWaker;
+use servo::compositing::windowing::{WindowEvent, WindowMethods};
+use servo::euclid::{Point2D, Size2D, TypedPoint2D, TypedRect, TypedScale, TypedSize2D};
+use servo::gl;
+use servo::ipc_channel::ipc;
+use servo::msg::constellation_msg::{Key, KeyModifiers, KeyState, TraversalDirection};
+use servo::net_traits::net_error_list::NetError;
+use servo::script_traits::LoadData;
+use servo::servo_geometry::DeviceIndependentPixel;
+use servo::servo_url::ServoUrl;
+use servo::style_traits::cursor::CursorKind;
+use servo::style_traits::DevicePixel;
+use servo::{BrowserId, Servo};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::Arc;
+
+thread_local! {
+    static WINDOWS: RefCell<HashMap<glutin::WindowId, Rc<WindowEntry>>> =
+        RefCell::new(HashMap::new());
+    static FOCUSED: Cell<Option<glutin::WindowId>> = Cell::new(None);
+}
+
+pub struct GlutinEventLoopWaker {
+    proxy: Arc<glutin::EventsLoopProxy>,
+}
+
+impl GlutinEventLoopWaker {
+    pub fn new(event_loop: &glutin::EventsLoop) -> GlutinEventLoopWaker {
+        GlutinEventLoopWaker {
+            proxy: Arc::new(event_loop.create_proxy()),
+        }
+    }
+}
+
+impl EventLoopWaker for GlutinEventLoopWaker {
+    // Used by servo to share the "event loop waker" across threads
+    fn clone(&self) -> Box<EventLoopWaker + Send> {
+        Box::new(GlutinEventLoopWaker {
+            proxy: self.proxy.clone(),
+        })
+    }
+    // Called by servo when the main thread needs to wake up
+    fn wake(&self) {
+        self.proxy.wakeup().expect("wakeup eventloop failed");
+    }
+}
+
+/// The GL-backed window a single top-level browser renders into. Owns a
+/// `Browser` delegate for each browser open in it, so the
+/// `WindowMethods` callbacks (which only get a `BrowserId`, not a
+/// `WindowEntry`) have somewhere to record navigation/chrome state.
+pub struct Window {
+    window_id: glutin::WindowId,
+    gl_context: RefCell<GlContext>,
+    // Only set for `--headless` windows: a hidden window that exists
+    // purely so this `Window` still has a `WindowId` to be dispatched to
+    // and a winit handle to answer chrome queries with. Its own GL
+    // context (if it even has one) is never used for rendering; that
+    // happens through `gl_context`'s offscreen surface instead.
+    headless_window: Option<glutin::Window>,
+    headless_size: Cell<(u32, u32)>,
+    // Multiplies CSS pixels only: `size()` shrinks as this grows, while
+    // `framebuffer_size()` (device pixels) and `hidpi_factor()` are
+    // unaffected. Set by the Ctrl+=/Ctrl+-/Ctrl+0 chrome shortcuts;
+    // trackpad pinch-zoom (`WindowEvent::PinchZoom`) is a separate,
+    // compositor-level scale that doesn't touch this.
+    page_zoom: Cell<f32>,
+    waker: Box<EventLoopWaker>,
+    gl: Rc<gl::Gl>,
+    browsers: RefCell<HashMap<BrowserId, Browser>>,
+    // `None` for `--headless` windows (there's no system clipboard worth
+    // touching in a displayless CI environment) and for any environment
+    // where no clipboard backend is available at all. `ClipboardContext`'s
+    // `get_contents`/`set_contents` both take `&mut self`, but
+    // `WindowMethods` callbacks only get `&self`.
+    clipboard: RefCell<Option<ClipboardContext>>,
+    // Keyboard state is per-window, not global: a key held in this window
+    // then released after focus moves elsewhere (or vice versa) must not
+    // desync another window's idea of what's held.
+    held_keys: RefCell<HashSet<glutin::VirtualKeyCode>>,
+    pending_char_key: RefCell<Option<(Key, KeyState, KeyModifiers)>>,
+    // Updated on `CursorMoved` in this window; `None` until it's seen one,
+    // in which case keyboard-driven scrolling falls back to `center()`.
+    // Per-window so moving the mouse in one window doesn't get reused as
+    // another window's scroll origin.
+    pointer: Cell<Option<(f64, f64)>>,
+}
+
+/// `page_zoom` is multiplied or divided by this for each Ctrl+=/Ctrl+-
+/// press, matching the step most browser chrome uses.
+const ZOOM_STEP: f32 = 1.1;
+const MIN_ZOOM: f32 = 0.3;
+const MAX_ZOOM: f32 = 3.0;
+
+/// Everything the registry keeps alive for one open window: its `Window`
+/// (GL context and chrome callbacks), the `Servo` instance compositing
+/// into it, and the id of the browser currently selected in it.
+pub struct WindowEntry {
+    pub window: Rc<Window>,
+    pub servo: RefCell<Servo<Window>>,
+    pub browser_id: Cell<Option<BrowserId>>,
+}
+
+impl WindowEntry {
+    /// Send `events` to Servo, then let the focused `Browser` fold
+    /// whatever its `WindowMethods` callbacks queued up (title/URL/history
+    /// changes, navigation requests, ...) into its own chrome state.
+    pub fn dispatch(&self, events: Vec<WindowEvent>) {
+        self.servo.borrow_mut().handle_events(events);
+        if let Some(browser_id) = self.browser_id.get() {
+            self.window.pump_browser(browser_id);
+        }
+    }
+}
+
+const DEFAULT_SIZE: (u32, u32) = (800, 600);
+
+/// Create a new top-level window, register it under its `WindowId`,
+/// focus it, and navigate it to `url`. Returns the id the registry filed
+/// it under.
+///
+/// When `headless` is set, no GL surface is ever presented on screen: a
+/// hidden window is still created so this `Window` has somewhere to
+/// answer chrome queries from and a `WindowId` to be dispatched to, but
+/// rendering goes through an offscreen context instead of that window's
+/// own.
+pub fn create_window(
+    event_loop: &glutin::EventsLoop,
+    url: ServoUrl,
+    headless: bool,
+) -> glutin::WindowId {
+    let gl_version = glutin::GlRequest::Specific(glutin::Api::OpenGl, (3, 2));
+
+    let (gl_context, headless_window) = if headless {
+        // There's only one real current GL context per thread. Building
+        // this one and making it current would otherwise silently bump
+        // whichever other headless window's context the OS considers
+        // current right now, without that window's `GlContext` wrapper
+        // ever learning about the hand-off.
+        release_current_headless_context();
+        let context = glutin::ContextBuilder::new()
+            .with_gl(gl_version)
+            .build_headless(
+                event_loop,
+                glutin::dpi::PhysicalSize::new(DEFAULT_SIZE.0 as f64, DEFAULT_SIZE.1 as f64),
+            )
+            .unwrap();
+        let context = unsafe {
+            context
+                .make_current()
+                .expect("Couldn't make context current")
+        };
+        let hidden_window = glutin::WindowBuilder::new()
+            .with_visibility(false)
+            .with_dimensions(DEFAULT_SIZE.0, DEFAULT_SIZE.1)
+            .build(event_loop)
+            .unwrap();
+        (GlContext::headless(context), Some(hidden_window))
+    } else {
+        let builder = glutin::WindowBuilder::new().with_dimensions(DEFAULT_SIZE.0, DEFAULT_SIZE.1);
+        let context = glutin::ContextBuilder::new()
+            .with_gl(gl_version)
+            .with_vsync(true)
+            .build_windowed(builder, event_loop)
+            .unwrap();
+        let context = unsafe {
+            context
+                .make_current()
+                .expect("Couldn't make window current")
+        };
+        context.window().show();
+        (GlContext::windowed(context), None)
+    };
+
+    let window_id = match (&gl_context, &headless_window) {
+        (GlContext::Current(ctx), _) => ctx.window().id(),
+        (_, Some(hidden_window)) => hidden_window.id(),
+        _ => unreachable!("just built either a Current or a headless+hidden window"),
+    };
+
+    let gl = unsafe { gl::GlFns::load_with(|s| gl_context.get_proc_address(s) as *const _) };
+    let waker = Box::new(GlutinEventLoopWaker::new(event_loop));
+
+    let window = Rc::new(Window {
+        window_id,
+        gl_context: RefCell::new(gl_context),
+        headless_window,
+        headless_size: Cell::new(DEFAULT_SIZE),
+        page_zoom: Cell::new(1.0),
+        waker,
+        gl,
+        browsers: RefCell::new(HashMap::new()),
+        clipboard: RefCell::new(if headless {
+            None
+        } else {
+            match ClipboardContext::new() {
+                Ok(context) => Some(context),
+                Err(error) => {
+                    eprintln!("No clipboard backend available: {}", error);
+                    None
+                }
+            }
+        }),
+        held_keys: RefCell::new(HashSet::new()),
+        pending_char_key: RefCell::new(None),
+        pointer: Cell::new(None),
+    });
+
+    let mut servo = Servo::new(window.clone());
+    let (sender, receiver) = ipc::channel().unwrap();
+    servo.handle_events(vec![WindowEvent::NewBrowser(url, sender)]);
+    let browser_id = receiver.recv().unwrap();
+    window.register_browser(browser_id);
+    servo.handle_events(vec![WindowEvent::SelectBrowser(browser_id)]);
+    // No context stays current between events: `with_window` and
+    // `wake_all` make this one current again whenever it is pumped.
+    window.make_not_current();
+
+    let entry = Rc::new(WindowEntry {
+        window,
+        servo: RefCell::new(servo),
+        browser_id: Cell::new(Some(browser_id)),
+    });
+
+    WINDOWS.with(|windows| windows.borrow_mut().insert(window_id, entry));
+    FOCUSED.with(|focused| focused.set(Some(window_id)));
+
+    window_id
+}
+
+/// Tear down `window_id`'s browser and window. Returns `true` if no
+/// windows are left open, i.e. the caller should stop the event loop.
+pub fn close_window(window_id: glutin::WindowId) -> bool {
+    WINDOWS.with(|windows| {
+        let mut windows = windows.borrow_mut();
+        if let Some(entry) = windows.remove(&window_id) {
+            if let Some(browser_id) = entry.browser_id.get() {
+                entry
+                    .servo
+                    .borrow_mut()
+                    .handle_events(vec![WindowEvent::CloseBrowser(browser_id)]);
+                entry.window.unregister_browser(browser_id);
+            }
+        }
+        FOCUSED.with(|focused| {
+            if focused.get() == Some(window_id) {
+                focused.set(windows.keys().next().cloned());
+            }
+        });
+        windows.is_empty()
+    })
+}
+
+/// The window that should receive chrome shortcuts (Ctrl+T, Ctrl+W, ...).
+pub fn focused_window_id() -> Option<glutin::WindowId> {
+    FOCUSED.with(|focused| focused.get())
+}
+
+pub fn set_focused_window_id(window_id: glutin::WindowId) {
+    FOCUSED.with(|focused| focused.set(Some(window_id)));
+}
+
+/// Run `f` with the registry entry for `window_id`, if it's still open.
+/// Makes `window_id`'s GL context current first and releases it again
+/// afterwards, so whichever window was current before this call (if any)
+/// is left untouched and the compositing `f` does happens into the right
+/// window's surface.
+pub fn with_window<F, R>(window_id: glutin::WindowId, f: F) -> Option<R>
+where
+    F: FnOnce(&WindowEntry) -> R,
+{
+    WINDOWS.with(|windows| {
+        windows.borrow().get(&window_id).map(|entry| {
+            entry.window.make_current();
+            let result = f(entry);
+            entry.window.make_not_current();
+            result
+        })
+    })
+}
+
+/// Pump every open `Servo` instance with no new events, draining the work
+/// queued by a `GlutinEventLoopWaker::wake()` call.
+pub fn wake_all() {
+    let entries: Vec<Rc<WindowEntry>> =
+        WINDOWS.with(|windows| windows.borrow().values().cloned().collect());
+    for entry in entries {
+        entry.window.make_current();
+        entry.dispatch(vec![]);
+        entry.window.make_not_current();
+    }
+}
+
+/// Release whichever already-registered headless window currently holds
+/// the thread's real current GL context, if any. Only one headless (or
+/// windowed) context can really be current on a thread at a time, so a
+/// brand new headless context must not be made current while another
+/// window's `GlContext` still believes itself to be.
+fn release_current_headless_context() {
+    WINDOWS.with(|windows| {
+        for entry in windows.borrow().values() {
+            let is_current = match *entry.window.gl_context.borrow() {
+                GlContext::HeadlessCurrent(_) => true,
+                _ => false,
+            };
+            if is_current {
+                entry.window.make_not_current();
+                break;
+            }
+        }
+    });
+}
+
+impl Window {
+    /// Run `f` with whichever winit window this `Window` has available
+    /// for chrome queries: the real one in windowed mode, the hidden
+    /// placeholder in headless mode.
+    fn with_winit_window<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&glutin::Window) -> R,
+    {
+        match (&*self.gl_context.borrow(), &self.headless_window) {
+            (GlContext::Current(ctx), _) => f(ctx.window()),
+            (GlContext::NotCurrent(ctx), _) => f(ctx.window()),
+            (_, Some(hidden_window)) => f(hidden_window),
+            _ => panic!("No winit window available to query"),
+        }
+    }
+
+    pub fn resize(&self, width: u32, height: u32) {
+        if self.gl_context.borrow().is_headless() {
+            self.headless_size.set((width, height));
+            return;
+        }
+        self.gl_context
+            .borrow()
+            .resize(glutin::dpi::PhysicalSize::new(width as f64, height as f64));
+    }
+
+    /// The window's center, in device-independent pixels, used as the
+    /// scroll origin for keyboard shortcuts when there's no recent
+    /// pointer position to reuse.
+    pub fn center(&self) -> (f64, f64) {
+        let (width, height) = self.inner_size();
+        (width as f64 / 2.0, height as f64 / 2.0)
+    }
+
+    fn inner_size(&self) -> (u32, u32) {
+        if self.gl_context.borrow().is_headless() {
+            return self.headless_size.get();
+        }
+        self.with_winit_window(|w| w.get_inner_size().unwrap())
+    }
+
+    fn raw_hidpi_factor(&self) -> f32 {
+        if self.gl_context.borrow().is_headless() {
+            // No real display to ask, so render 1:1.
+            return 1.0;
+        }
+        self.with_winit_window(|w| w.hidpi_factor())
+    }
+
+    /// Make this window's GL context current on this thread. Needed
+    /// before switching which window's framebuffer subsequent GL calls
+    /// draw into.
+    pub fn make_current(&self) {
+        self.gl_context.borrow_mut().make_current();
+    }
+
+    /// Release this thread's claim on this window's GL context, so
+    /// another window's context can be made current instead.
+    pub fn make_not_current(&self) {
+        self.gl_context.borrow_mut().make_not_current();
+    }
+
+    /// The current page-zoom multiplier, for chrome to display.
+    pub fn zoom_level(&self) -> f32 {
+        self.page_zoom.get()
+    }
+
+    /// Step the page zoom in one `ZOOM_STEP`, clamped to `MAX_ZOOM`, and
+    /// return the relative factor Servo should apply.
+    pub fn zoom_in(&self) -> f32 {
+        let zoom = (self.page_zoom.get() * ZOOM_STEP).min(MAX_ZOOM);
+        let relative = zoom / self.page_zoom.get();
+        self.page_zoom.set(zoom);
+        relative
+    }
+
+    /// Step the page zoom out one `ZOOM_STEP`, clamped to `MIN_ZOOM`, and
+    /// return the relative factor Servo should apply.
+    pub fn zoom_out(&self) -> f32 {
+        let zoom = (self.page_zoom.get() / ZOOM_STEP).max(MIN_ZOOM);
+        let relative = zoom / self.page_zoom.get();
+        self.page_zoom.set(zoom);
+        relative
+    }
+
+    /// Reset the page zoom back to 100%.
+    pub fn reset_zoom(&self) {
+        self.page_zoom.set(1.0);
+    }
+
+    /// Start tracking chrome/navigation state for a newly created browser.
+    fn register_browser(&self, id: BrowserId) {
+        self.browsers.borrow_mut().insert(id, Browser::new(id));
+    }
+
+    /// Stop tracking a browser once it (and its window, or its tab) has
+    /// closed.
+    fn unregister_browser(&self, id: BrowserId) {
+        self.browsers.borrow_mut().remove(&id);
+    }
+
+    /// Drain `id`'s queued `WindowMethods` callbacks into its chrome state.
+    fn pump_browser(&self, id: BrowserId) {
+        if let Some(browser) = self.browsers.borrow().get(&id) {
+            browser.handle_servo_events();
+        }
+    }
+
+    /// The URL currently loaded in browser `id`, if we've seen one yet.
+    pub fn url(&self, id: Option<BrowserId>) -> Option<ServoUrl> {
+        let id = id?;
+        self.browsers.borrow().get(&id)?.url()
+    }
+
+    /// Build the `WindowEvent` that sends browser `id` back (`Back`) or
+    /// forward (`Forward`) through its history.
+    pub fn navigate_event(&self, id: BrowserId, direction: TraversalDirection) -> WindowEvent {
+        match self.browsers.borrow().get(&id) {
+            Some(browser) => browser.navigate(direction),
+            None => WindowEvent::Navigation(id, direction),
+        }
+    }
+
+    /// Whether `keycode` was already held down in this window, i.e.
+    /// whether a further `Pressed` event for it is the OS auto-repeating
+    /// rather than a fresh press.
+    pub fn is_key_held(&self, keycode: glutin::VirtualKeyCode) -> bool {
+        self.held_keys.borrow().contains(&keycode)
+    }
+
+    /// Record whether `keycode` is currently held down in this window.
+    pub fn set_key_held(&self, keycode: glutin::VirtualKeyCode, held: bool) {
+        if held {
+            self.held_keys.borrow_mut().insert(keycode);
+        } else {
+            self.held_keys.borrow_mut().remove(&keycode);
+        }
+    }
+
+    /// Stash a char-producing key press, to be combined with the
+    /// `ReceivedCharacter` that follows it into one `WindowEvent::KeyEvent`.
+    pub fn set_pending_char_key(&self, pending: (Key, KeyState, KeyModifiers)) {
+        *self.pending_char_key.borrow_mut() = Some(pending);
+    }
+
+    /// Take this window's stashed char-producing key press, if any.
+    pub fn take_pending_char_key(&self) -> Option<(Key, KeyState, KeyModifiers)> {
+        self.pending_char_key.borrow_mut().take()
+    }
+
+    /// This window's last-known mouse position, or `None` if it hasn't
+    /// seen a `CursorMoved` yet.
+    pub fn pointer(&self) -> Option<(f64, f64)> {
+        self.pointer.get()
+    }
+
+    /// Record this window's mouse position, as reported by its own
+    /// `CursorMoved` event.
+    pub fn set_pointer(&self, x: f64, y: f64) {
+        self.pointer.set(Some((x, y)));
+    }
+}
+
+impl WindowMethods for Window {
+    fn prepare_for_composite(&self, _width: usize, _height: usize) -> bool {
+        true
+    }
+
+    fn present(&self) {
+        self.gl_context.borrow().present();
+    }
+
+    fn supports_clipboard(&self) -> bool {
+        self.clipboard.borrow().is_some()
+    }
+
+    fn clipboard_contents(&self) -> Option<String> {
+        self.clipboard
+            .borrow_mut()
+            .as_mut()
+            .and_then(|context| context.get_contents().ok())
+    }
+
+    fn set_clipboard_contents(&self, contents: String) {
+        if let Some(context) = self.clipboard.borrow_mut().as_mut() {
+            if let Err(error) = context.set_contents(contents) {
+                eprintln!("Failed to set clipboard contents: {}", error);
+            }
+        }
+    }
+
+    fn create_event_loop_waker(&self) -> Box<EventLoopWaker> {
+        self.waker.clone()
+    }
+
+    fn gl(&self) -> Rc<gl::Gl> {
+        self.gl.clone()
+    }
+
+    fn hidpi_factor(&self) -> TypedScale<f32, DeviceIndependentPixel, DevicePixel> {
+        TypedScale::new(self.raw_hidpi_factor())
+    }
+
+    fn framebuffer_size(&self) -> TypedSize2D<u32, DevicePixel> {
+        let (width, height) = self.inner_size();
+        let scale_factor = self.raw_hidpi_factor() as u32;
+        TypedSize2D::new(scale_factor * width, scale_factor * height)
+    }
+
+    fn window_rect(&self) -> TypedRect<u32, DevicePixel> {
+        TypedRect::new(TypedPoint2D::new(0, 0), self.framebuffer_size())
+    }
+
+    fn size(&self) -> TypedSize2D<f32, DeviceIndependentPixel> {
+        let (width, height) = self.inner_size();
+        let zoom = self.page_zoom.get();
+        TypedSize2D::new(width as f32 / zoom, height as f32 / zoom)
+    }
+
+    fn client_window(&self, _id: BrowserId) -> (Size2D<u32>, Point2D<i32>) {
+        let (width, height) = self.inner_size();
+        if self.gl_context.borrow().is_headless() {
+            return (Size2D::new(width, height), Point2D::new(0, 0));
+        }
+        let (x, y) = self.with_winit_window(|w| w.get_position().unwrap());
+        (Size2D::new(width, height), Point2D::new(x as i32, y as i32))
+    }
+
+    fn set_inner_size(&self, _id: BrowserId, _size: Size2D<u32>) {}
+
+    fn set_position(&self, _id: BrowserId, _point: Point2D<i32>) {}
+
+    fn set_fullscreen_state(&self, _id: BrowserId, _state: bool) {}
+
+    fn set_page_title(&self, id: BrowserId, title: Option<String>) {
+        let title_str = title.as_ref().map_or("", |title| title.as_str());
+        self.with_winit_window(|w| w.set_title(title_str));
+        if let Some(browser) = self.browsers.borrow().get(&id) {
+            browser.on_title_changed(title);
+        }
+    }
+
+    fn status(&self, id: BrowserId, status: Option<String>) {
+        if let Some(browser) = self.browsers.borrow().get(&id) {
+            browser.on_status_changed(status);
+        }
+    }
+
+    fn allow_navigation(&self, id: BrowserId, url: ServoUrl, chan: ipc::IpcSender<bool>) {
+        match self.browsers.borrow().get(&id) {
+            Some(browser) => browser.on_navigation_requested(url, chan),
+            // No delegate yet (e.g. the very first navigation of a brand
+            // new browser): fall back to allowing it outright.
+            None => {
+                chan.send(true).ok();
+            }
+        }
+    }
+
+    fn load_start(&self, id: BrowserId) {
+        if let Some(browser) = self.browsers.borrow().get(&id) {
+            browser.on_load_start();
+        }
+    }
+
+    fn load_end(&self, id: BrowserId) {
+        if let Some(browser) = self.browsers.borrow().get(&id) {
+            browser.on_load_end();
+        }
+    }
+
+    fn load_error(&self, id: BrowserId, error: NetError, url: String) {
+        if let Some(browser) = self.browsers.borrow().get(&id) {
+            browser.on_load_error(error, url);
+        }
+    }
+
+    fn head_parsed(&self, _id: BrowserId) {}
+
+    fn history_changed(&self, id: BrowserId, entries: Vec<LoadData>, current: usize) {
+        if let Some(browser) = self.browsers.borrow().get(&id) {
+            browser.on_history_changed(entries, current);
+        }
+    }
+
+    fn set_cursor(&self, cursor: CursorKind) {
+        let cursor = match cursor {
+            CursorKind::Pointer => glutin::MouseCursor::Hand,
+            _ => glutin::MouseCursor::Default,
+        };
+        self.with_winit_window(|w| w.set_cursor(cursor));
+    }
+
+    fn set_favicon(&self, id: BrowserId, url: ServoUrl) {
+        if let Some(browser) = self.browsers.borrow().get(&id) {
+            browser.on_favicon_changed(url);
+        }
+    }
+
+    fn handle_key(
+        &self,
+        _id: Option<BrowserId>,
+        _ch: Option<char>,
+        _key: Key,
+        _mods: KeyModifiers,
+    ) {
+    }
+
+    fn handle_panic(&self, _id: BrowserId, _reason: String, _backtrace: Option<String>) {}
+
+    fn screen_avail_size(&self, _id: BrowserId) -> Size2D<u32> {
+        if self.gl_context.borrow().is_headless() {
+            let (width, height) = self.headless_size.get();
+            return Size2D::new(width, height);
+        }
+        self.with_winit_window(|w| {
+            let (width, height) = w.get_current_monitor().get_dimensions();
+            Size2D::new(width, height)
+        })
+    }
+
+    fn screen_size(&self, id: BrowserId) -> Size2D<u32> {
+        self.screen_avail_size(id)
+    }
+}